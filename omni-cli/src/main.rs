@@ -0,0 +1,134 @@
+use clap::{Parser, Subcommand};
+use minicose::CoseSign1;
+use omni::identity::cose::CoseKeyIdentity;
+use omni::message::request::RequestMessageBuilder;
+use omni::message::{decode_request_from_cose_sign1, encode_cose_sign1_from_request};
+use omni::Identity;
+use std::path::PathBuf;
+
+/// Key-management CLI for OMNI identities.
+#[derive(Parser)]
+#[clap(name = "omni-cli")]
+struct Opts {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate a new Ed25519 PEM and print its derived OMNI identity.
+    Generate {
+        /// Where to write the generated PEM file.
+        #[clap(long)]
+        pem: PathBuf,
+    },
+
+    /// Print the identity string and key kind of a PEM file. The public key
+    /// itself is not printed.
+    Info {
+        /// The PEM file to inspect.
+        pem: PathBuf,
+    },
+
+    /// Sign a message with a PEM file, emitting a CoSE Sign1 envelope.
+    Sign {
+        /// The PEM file of the identity signing the message.
+        pem: PathBuf,
+
+        /// The message to sign.
+        message: String,
+    },
+
+    /// Verify a CoSE Sign1 envelope and report the recovered identity.
+    Verify {
+        /// Path to the file containing the hex-encoded envelope.
+        envelope: PathBuf,
+    },
+
+    /// Reconstruct an identity from a raw public key.
+    Recover {
+        /// The public key bytes, hex-encoded.
+        public_key: String,
+    },
+}
+
+fn main() {
+    let opts = Opts::parse();
+
+    match opts.command {
+        Command::Generate { pem } => generate(&pem),
+        Command::Info { pem } => info(&pem),
+        Command::Sign { pem, message } => sign(&pem, &message),
+        Command::Verify { envelope } => verify(&envelope),
+        Command::Recover { public_key } => recover(&public_key),
+    }
+}
+
+fn generate(pem: &PathBuf) {
+    let identity = CoseKeyIdentity::generate_ed25519().expect("Could not generate a keypair.");
+    std::fs::write(pem, identity.to_pem().expect("Could not encode the PEM file."))
+        .expect("Could not write the PEM file.");
+
+    println!("Wrote a new identity to {}", pem.display());
+    println!("identity: {}", identity.identity);
+}
+
+fn info(pem: &PathBuf) {
+    let content = std::fs::read_to_string(pem).expect("Could not read the PEM file.");
+    let identity = CoseKeyIdentity::from_pem(&content).expect("Could not parse the PEM file.");
+
+    let kind = if identity.identity.is_anonymous() {
+        "anonymous"
+    } else if identity.identity.is_public_key() {
+        "public-key"
+    } else if identity.identity.is_addressable() {
+        "addressable"
+    } else {
+        "unknown"
+    };
+
+    println!("identity: {}", identity.identity);
+    println!("kind:     {}", kind);
+}
+
+fn sign(pem: &PathBuf, message: &str) {
+    let content = std::fs::read_to_string(pem).expect("Could not read the PEM file.");
+    let identity = CoseKeyIdentity::from_pem(&content).expect("Could not parse the PEM file.");
+
+    let request = RequestMessageBuilder::default()
+        .from(identity.identity)
+        .method("cli.sign".to_string())
+        .data(message.as_bytes().to_vec())
+        .build()
+        .expect("Could not build the request message.");
+
+    let envelope = encode_cose_sign1_from_request(request, identity.identity, &identity.key)
+        .expect("Could not sign the message.");
+
+    let bytes = envelope.to_bytes().expect("Could not encode the envelope.");
+    println!("{}", hex::encode(bytes));
+}
+
+fn verify(envelope: &PathBuf) {
+    let content = std::fs::read_to_string(envelope).expect("Could not read the envelope file.");
+    let bytes = hex::decode(content.trim()).expect("Envelope file must be hex-encoded.");
+    let sign1 = CoseSign1::from_bytes(&bytes).expect("Could not parse the envelope.");
+
+    let request = decode_request_from_cose_sign1(sign1, None)
+        .expect("The envelope did not verify or could not be decoded.");
+
+    println!(
+        "from: {}",
+        request.from.unwrap_or_else(Identity::anonymous)
+    );
+}
+
+fn recover(public_key: &str) {
+    let key_bytes = hex::decode(public_key).expect("Public key must be hex-encoded.");
+    let cose_key = minicose::Ed25519CoseKeyBuilder::default()
+        .x(key_bytes)
+        .build()
+        .expect("Could not build a COSE key from the public key.");
+
+    println!("identity: {}", Identity::public_key(&cose_key));
+}
@@ -9,14 +9,86 @@ pub use response::ResponseMessage;
 pub use response::ResponseMessageBuilder;
 
 use crate::Identity;
+use k256::ecdsa::signature::{Signer, Verifier};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
 use minicose::exports::ciborium::value::Value;
 use minicose::{
     AlgorithmicCurve, Algorithms, CoseKey, CoseKeySet, CoseSign1, CoseSign1Builder, Ed25519CoseKey,
-    Ed25519CoseKeyBuilder, ProtectedHeaders, ProtectedHeadersBuilder,
+    Ed25519CoseKeyBuilder, Ec2CoseKey, Ec2CoseKeyBuilder, ProtectedHeaders, ProtectedHeadersBuilder,
 };
 use ring::signature::KeyPair;
 use std::convert::TryFrom;
 
+/// A signing key backing an OMNI [`Identity`], either Ed25519 or secp256k1.
+///
+/// `omni::identity::cose::CoseKeyIdentity` (the type `omni-cli` and every
+/// other caller in this tree actually carry a key around in) isn't present
+/// in this source tree, and no code here constructs a `Secp256k1` key. The
+/// `encode_*` functions sign with one when a caller supplies it, and
+/// `get_public_key_for_identity` verifies secp256k1 envelopes signed elsewhere.
+pub enum SigningKey {
+    Ed25519(ring::signature::Ed25519KeyPair),
+    Secp256k1(k256::ecdsa::SigningKey),
+}
+
+impl SigningKey {
+    fn algorithm(&self) -> Algorithms {
+        match self {
+            SigningKey::Ed25519(_) => Algorithms::EdDSA(AlgorithmicCurve::Ed25519),
+            SigningKey::Secp256k1(_) => Algorithms::ES256K(AlgorithmicCurve::Secp256k1),
+        }
+    }
+
+    fn cose_public_key(&self, id: &Identity) -> CoseKey {
+        match self {
+            SigningKey::Ed25519(kp) => Ed25519CoseKeyBuilder::default()
+                .x(kp.public_key().as_ref().to_vec())
+                .kid(id.to_vec())
+                .build()
+                .unwrap()
+                .into(),
+            SigningKey::Secp256k1(sk) => {
+                let point = sk.verifying_key().to_encoded_point(false);
+                Ec2CoseKeyBuilder::default()
+                    .crv(AlgorithmicCurve::Secp256k1)
+                    .x(point.x().unwrap().to_vec())
+                    .y(point.y().unwrap().to_vec())
+                    .kid(id.to_vec())
+                    .build()
+                    .unwrap()
+                    .into()
+            }
+        }
+    }
+
+    fn sign(&self, bytes: &[u8]) -> Vec<u8> {
+        match self {
+            SigningKey::Ed25519(kp) => kp.sign(bytes).as_ref().to_vec(),
+            SigningKey::Secp256k1(sk) => {
+                let sig: k256::ecdsa::Signature = sk.sign(bytes);
+                sig.to_vec()
+            }
+        }
+    }
+}
+
+/// A verifying key recovered from a CoSE envelope's keyset.
+enum VerifyingKey {
+    Ed25519(ring::signature::UnparsedPublicKey<Vec<u8>>),
+    Secp256k1(k256::ecdsa::VerifyingKey),
+}
+
+impl VerifyingKey {
+    fn verify(&self, content: &[u8], sig: &[u8]) -> bool {
+        match self {
+            VerifyingKey::Ed25519(key) => key.verify(content, sig).is_ok(),
+            VerifyingKey::Secp256k1(key) => k256::ecdsa::Signature::try_from(sig)
+                .map(|sig| key.verify(content, &sig).is_ok())
+                .unwrap_or(false),
+        }
+    }
+}
+
 pub fn decode_request_from_cose_sign1(
     sign1: CoseSign1,
     to: Option<Identity>,
@@ -79,24 +151,21 @@ pub fn decode_response_from_cose_sign1(
 fn encode_cose_sign1_from_payload(
     payload: Vec<u8>,
     id: Identity,
-    keypair: &Option<ring::signature::Ed25519KeyPair>,
+    keypair: &Option<SigningKey>,
 ) -> Result<CoseSign1, String> {
-    let maybe_cose_key: Option<CoseKey> = keypair.as_ref().map(|kp| {
-        let x = kp.public_key().as_ref().to_vec();
-        Ed25519CoseKeyBuilder::default()
-            .x(x)
-            .kid(id.to_vec())
-            .build()
-            .unwrap()
-            .into()
-    });
+    let maybe_cose_key: Option<CoseKey> = keypair.as_ref().map(|kp| kp.cose_public_key(&id));
 
     if !id.matches_key(&maybe_cose_key) {
         return Err("Identity did not match keypair.".to_string());
     }
 
+    let alg = keypair
+        .as_ref()
+        .map(SigningKey::algorithm)
+        .unwrap_or(Algorithms::EdDSA(AlgorithmicCurve::Ed25519));
+
     let mut protected: ProtectedHeaders = ProtectedHeadersBuilder::default()
-        .alg(Algorithms::EdDSA(AlgorithmicCurve::Ed25519))
+        .alg(alg)
         .kid(id.to_vec())
         .content_type("application/cbor".to_string())
         .build()
@@ -120,7 +189,7 @@ fn encode_cose_sign1_from_payload(
         .unwrap();
 
     if let Some(ref kp) = keypair {
-        cose.sign_with(|bytes| Ok(kp.sign(bytes).as_ref().to_vec()))
+        cose.sign_with(|bytes| Ok(kp.sign(bytes)))
             .map_err(|e| e.to_string())?;
     }
     Ok(cose)
@@ -129,7 +198,7 @@ fn encode_cose_sign1_from_payload(
 pub fn encode_cose_sign1_from_response(
     response: ResponseMessage,
     id: Identity,
-    keypair: &Option<ring::signature::Ed25519KeyPair>,
+    keypair: &Option<SigningKey>,
 ) -> Result<CoseSign1, String> {
     encode_cose_sign1_from_payload(response.to_bytes().unwrap(), id, keypair)
 }
@@ -137,7 +206,7 @@ pub fn encode_cose_sign1_from_response(
 pub fn encode_cose_sign1_from_request(
     request: RequestMessage,
     id: Identity,
-    keypair: &Option<ring::signature::Ed25519KeyPair>,
+    keypair: &Option<SigningKey>,
 ) -> Result<CoseSign1, String> {
     encode_cose_sign1_from_payload(request.to_bytes().unwrap(), id, keypair)
 }
@@ -159,41 +228,44 @@ impl CoseSign1RequestMessage {
         }
     }
 
-    pub fn get_public_key_for_identity(
-        &self,
-        id: &Identity,
-    ) -> Option<ring::signature::UnparsedPublicKey<Vec<u8>>> {
+    fn get_public_key_for_identity(&self, id: &Identity) -> Option<VerifyingKey> {
         // Verify the keybytes matches the identity.
         if id.is_anonymous() {
             return None;
         }
         // Find the key_bytes.
         let cose_key = self.get_keyset()?.get_kid(&id.to_vec()).cloned()?;
-        let ed25519_key = Ed25519CoseKey::try_from(cose_key.clone()).ok()?;
-        let key_bytes = ed25519_key.x?;
 
-        if id.is_public_key() {
-            let other = Identity::public_key(&cose_key);
-            if other.eq(id) {
-                Some(ring::signature::UnparsedPublicKey::new(
-                    &ring::signature::ED25519,
-                    key_bytes,
-                ))
-            } else {
-                None
-            }
+        let matches = if id.is_public_key() {
+            Identity::public_key(&cose_key).eq(id)
         } else if id.is_addressable() {
-            if Identity::addressable(&cose_key).eq(id) {
-                // Some(cosekey_to_ring_key(key_bytes))
-                Some(ring::signature::UnparsedPublicKey::new(
+            Identity::addressable(&cose_key).eq(id)
+        } else {
+            false
+        };
+        if !matches {
+            return None;
+        }
+
+        // Dispatch on the envelope's declared algorithm.
+        match self.sign1.protected.alg {
+            Some(Algorithms::ES256K(_)) => {
+                let ec2_key = Ec2CoseKey::try_from(cose_key).ok()?;
+                let mut sec1 = vec![0x04];
+                sec1.extend_from_slice(&ec2_key.x?);
+                sec1.extend_from_slice(&ec2_key.y?);
+                k256::ecdsa::VerifyingKey::from_sec1_bytes(&sec1)
+                    .ok()
+                    .map(VerifyingKey::Secp256k1)
+            }
+            _ => {
+                let ed25519_key = Ed25519CoseKey::try_from(cose_key).ok()?;
+                let key_bytes = ed25519_key.x?;
+                Some(VerifyingKey::Ed25519(ring::signature::UnparsedPublicKey::new(
                     &ring::signature::ED25519,
                     key_bytes,
-                ))
-            } else {
-                None
+                )))
             }
-        } else {
-            None
         }
     }
 
@@ -208,7 +280,7 @@ impl CoseSign1RequestMessage {
                     .ok_or("Could not find a public key in the envelope".to_string())
                     .and_then(|key| {
                         self.sign1
-                            .verify_with(|content, sig| key.verify(content, sig).is_ok())
+                            .verify_with(|content, sig| key.verify(content, sig))
                             .map_err(|e| e.to_string())
                     })
                     .and_then(|valid| {
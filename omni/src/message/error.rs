@@ -1,12 +1,289 @@
-use minicbor::data::Type;
+use minicbor::data::{Tag, Type};
 use minicbor::encode::{Error, Write};
 use minicbor::{Decode, Decoder, Encode, Encoder};
 use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 use std::iter::FromIterator;
+use std::str::FromStr;
 
 pub const RESERVED_OMNI_ERROR_CODE: u32 = 10000;
 
+const TAG_ERROR_VALUE_INT: u64 = 40300;
+const TAG_ERROR_VALUE_FLOAT: u64 = 40301;
+const TAG_ERROR_VALUE_BOOL: u64 = 40302;
+const TAG_ERROR_VALUE_TIMESTAMP: u64 = 40303;
+const TAG_ERROR_CAUSE: u64 = 40304;
+
+/// How deep a decoded error cause chain is allowed to go.
+const MAX_ERROR_CAUSE_DEPTH: usize = 16;
+
+/// A typed value substituted into an [`OmniError`] template field.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ErrorValue {
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Timestamp(i64),
+}
+
+impl ErrorValue {
+    fn to_plain_string(&self) -> String {
+        match self {
+            ErrorValue::Str(s) => s.clone(),
+            ErrorValue::Int(n) => n.to_string(),
+            ErrorValue::Float(f) => f.to_string(),
+            ErrorValue::Bool(b) => b.to_string(),
+            ErrorValue::Timestamp(t) => t.to_string(),
+        }
+    }
+}
+
+impl From<String> for ErrorValue {
+    fn from(s: String) -> Self {
+        ErrorValue::Str(s)
+    }
+}
+
+/// A format specifier parsed out of a template field, e.g. the `bytes` in `{max:bytes}`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Conversion {
+    Bytes,
+    Integer,
+    Float,
+    Boolean,
+    Timestamp,
+    TimestampFmt(String),
+}
+
+impl FromStr for Conversion {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bytes" => Ok(Conversion::Bytes),
+            "integer" => Ok(Conversion::Integer),
+            "float" => Ok(Conversion::Float),
+            "boolean" => Ok(Conversion::Boolean),
+            "timestamp" => Ok(Conversion::Timestamp),
+            _ => s
+                .strip_prefix("timestamp(")
+                .and_then(|rest| rest.strip_suffix(')'))
+                .map(|fmt| Conversion::TimestampFmt(fmt.to_string()))
+                .ok_or(()),
+        }
+    }
+}
+
+impl Conversion {
+    fn apply(&self, value: &ErrorValue) -> String {
+        match (self, value) {
+            (Conversion::Bytes, ErrorValue::Int(n)) => format_bytes(*n),
+            (Conversion::Integer, ErrorValue::Int(n)) => n.to_string(),
+            (Conversion::Integer, ErrorValue::Float(f)) => (*f as i64).to_string(),
+            (Conversion::Float, ErrorValue::Float(f)) => f.to_string(),
+            (Conversion::Float, ErrorValue::Int(n)) => (*n as f64).to_string(),
+            (Conversion::Boolean, ErrorValue::Bool(b)) => b.to_string(),
+            (Conversion::Timestamp, ErrorValue::Timestamp(t)) => {
+                format_timestamp(*t, "%Y-%m-%dT%H:%M:%SZ")
+            }
+            (Conversion::TimestampFmt(fmt), ErrorValue::Timestamp(t)) => format_timestamp(*t, fmt),
+            _ => value.to_plain_string(),
+        }
+    }
+}
+
+fn format_bytes(n: i64) -> String {
+    const UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB", "PiB"];
+    let mut value = n.unsigned_abs() as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    let sign = if n < 0 { "-" } else { "" };
+    if unit == 0 {
+        format!("{sign}{value:.0} {}", UNITS[unit])
+    } else {
+        format!("{sign}{value:.2} {}", UNITS[unit])
+    }
+}
+
+fn format_timestamp(seconds_since_epoch: i64, fmt: &str) -> String {
+    chrono::NaiveDateTime::from_timestamp_opt(seconds_since_epoch, 0)
+        .map(|dt| dt.format(fmt).to_string())
+        .unwrap_or_else(|| seconds_since_epoch.to_string())
+}
+
+/// Where in a request an [`ErrorLabel`] points: a span or a method path.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ErrorLocation {
+    Span(std::ops::Range<u32>),
+    MethodPath(String),
+}
+
+/// A diagnostic annotation pointing at a specific part of a request.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ErrorLabel {
+    pub location: Option<ErrorLocation>,
+    pub annotation: String,
+}
+
+impl ErrorLabel {
+    pub fn new(annotation: impl Into<String>) -> Self {
+        Self {
+            location: None,
+            annotation: annotation.into(),
+        }
+    }
+
+    pub fn at_span(mut self, range: std::ops::Range<u32>) -> Self {
+        self.location = Some(ErrorLocation::Span(range));
+        self
+    }
+
+    pub fn at_method(mut self, path: impl Into<String>) -> Self {
+        self.location = Some(ErrorLocation::MethodPath(path.into()));
+        self
+    }
+}
+
+impl Display for ErrorLabel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.location {
+            Some(ErrorLocation::Span(range)) => {
+                write!(f, "[{}..{}] {}", range.start, range.end, self.annotation)
+            }
+            Some(ErrorLocation::MethodPath(path)) => write!(f, "[{path}] {}", self.annotation),
+            None => f.write_str(&self.annotation),
+        }
+    }
+}
+
+impl Encode for ErrorLocation {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
+        match self {
+            ErrorLocation::Span(range) => {
+                e.array(2)?.u32(range.start)?.u32(range.end)?;
+            }
+            ErrorLocation::MethodPath(path) => {
+                e.str(path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'b> Decode<'b> for ErrorLocation {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, minicbor::decode::Error> {
+        match d.datatype()? {
+            Type::Array => {
+                d.array()?;
+                let start = d.u32()?;
+                let end = d.u32()?;
+                Ok(ErrorLocation::Span(start..end))
+            }
+            _ => Ok(ErrorLocation::MethodPath(d.str()?.to_string())),
+        }
+    }
+}
+
+impl Encode for ErrorLabel {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
+        match &self.location {
+            Some(location) => e.array(2)?.encode(location)?.str(&self.annotation)?,
+            None => e.array(1)?.str(&self.annotation)?,
+        };
+        Ok(())
+    }
+}
+
+impl<'b> Decode<'b> for ErrorLabel {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, minicbor::decode::Error> {
+        let len = d.array()?;
+        if len == Some(2) {
+            let location = Some(d.decode()?);
+            let annotation = d.str()?.to_string();
+            Ok(ErrorLabel {
+                location,
+                annotation,
+            })
+        } else {
+            Ok(ErrorLabel {
+                location: None,
+                annotation: d.str()?.to_string(),
+            })
+        }
+    }
+}
+
+static APPLICATION_ERROR_REGISTRY: once_cell::sync::Lazy<std::sync::RwLock<BTreeMap<u32, &'static str>>> =
+    once_cell::sync::Lazy::new(|| std::sync::RwLock::new(BTreeMap::new()));
+
+/// Register an application's error codes and their description templates.
+/// Panics if a code falls below `RESERVED_OMNI_ERROR_CODE`.
+pub fn register_error_codes(codes: &[(u32, &'static str)]) {
+    for (code, _) in codes {
+        assert!(
+            *code >= RESERVED_OMNI_ERROR_CODE,
+            "Application error code {code} must be >= RESERVED_OMNI_ERROR_CODE ({RESERVED_OMNI_ERROR_CODE})."
+        );
+    }
+
+    let mut registry = APPLICATION_ERROR_REGISTRY
+        .write()
+        .expect("the application error code registry lock was poisoned");
+
+    for (code, description) in codes {
+        registry.insert(*code, description);
+    }
+}
+
+fn registered_message(code: u32) -> Option<&'static str> {
+    APPLICATION_ERROR_REGISTRY
+        .read()
+        .expect("the application error code registry lock was poisoned")
+        .get(&code)
+        .copied()
+}
+
+/// Companion to `omni_error!` for application-specific codes
+/// (`ApplicationSpecific(u32)`, `>= RESERVED_OMNI_ERROR_CODE`).
+#[macro_export]
+macro_rules! omni_app_error {
+    {
+        $(
+            $v: literal: $snake_name: ident ( $($arg: ident),* ) => $description: literal,
+        )*
+    } => {
+        pub const ERROR_CODES: &[(u32, &'static str)] = &[
+            $( ($v, $description), )*
+        ];
+
+        $(
+            #[doc = $description]
+            pub fn $snake_name( $($arg: String,)* ) -> $crate::message::error::OmniError {
+                debug_assert!(
+                    $v >= $crate::message::error::RESERVED_OMNI_ERROR_CODE,
+                    "Application error code {} must be >= RESERVED_OMNI_ERROR_CODE ({})",
+                    $v,
+                    $crate::message::error::RESERVED_OMNI_ERROR_CODE,
+                );
+                $crate::message::error::OmniError {
+                    code: $crate::message::error::OmniErrorCode::ApplicationSpecific($v),
+                    message: Some($description.to_string()),
+                    fields: std::collections::BTreeMap::from_iter(vec![
+                        $( (stringify!($arg).to_string(), $crate::message::error::ErrorValue::Str($arg)) ),*
+                    ]),
+                    labels: Vec::new(),
+                    cause: None,
+                }
+            }
+        )*
+    }
+}
+
 macro_rules! omni_error {
     {
         $(
@@ -24,7 +301,7 @@ macro_rules! omni_error {
             pub fn message(&self) -> Option<&'static str> {
                 match self {
                     $( OmniErrorCode::$name => Some($description), )*
-                    _ => None,
+                    OmniErrorCode::ApplicationSpecific(code) => registered_message(*code),
                 }
             }
         }
@@ -55,7 +332,9 @@ macro_rules! omni_error {
         pub struct OmniError {
             pub code: OmniErrorCode,
             pub message: Option<String>,
-            pub fields: BTreeMap<String, String>,
+            pub fields: BTreeMap<String, ErrorValue>,
+            pub labels: Vec<ErrorLabel>,
+            pub cause: Option<Box<OmniError>>,
         }
 
         impl OmniError {
@@ -66,8 +345,10 @@ macro_rules! omni_error {
                         code: OmniErrorCode::$name,
                         message: None,
                         fields: BTreeMap::from_iter(vec![
-                            $( (stringify!($arg).to_string(), $arg) ),*
+                            $( (stringify!($arg).to_string(), ErrorValue::Str($arg)) ),*
                         ]),
+                        labels: Vec::new(),
+                        cause: None,
                     }
                 }
             )?)*
@@ -118,6 +399,18 @@ impl OmniError {
     pub fn is_application_specific(&self) -> bool {
         self.code.is_application_specific()
     }
+
+    /// Attach a diagnostic label to this error.
+    pub fn with_label(mut self, label: ErrorLabel) -> Self {
+        self.labels.push(label);
+        self
+    }
+
+    /// Wrap a lower-level failure as this error's cause.
+    pub fn with_cause(mut self, cause: OmniError) -> Self {
+        self.cause = Some(Box::new(cause));
+        self
+    }
 }
 
 impl Default for OmniErrorCode {
@@ -157,60 +450,172 @@ impl Display for OmniError {
                 f.write_str("}")?;
             } else {
                 let field = &message[start + 1..end - 1];
-                f.write_str(self.fields.get(field).unwrap_or(&"".to_string()).as_str())?;
+                let (name, spec) = match field.split_once(':') {
+                    Some((name, spec)) => (name, Some(spec)),
+                    None => (field, None),
+                };
+
+                if let Some(value) = self.fields.get(name) {
+                    let rendered = match spec.and_then(|s| s.parse::<Conversion>().ok()) {
+                        Some(conversion) => conversion.apply(value),
+                        None => value.to_plain_string(),
+                    };
+                    f.write_str(&rendered)?;
+                }
             }
         }
-        f.write_str(&message[current..])
+        f.write_str(&message[current..])?;
+
+        for label in &self.labels {
+            write!(f, "\n  {label}")?;
+        }
+
+        if let Some(cause) = &self.cause {
+            write!(f, "\nCaused by: {cause}")?;
+        }
+        Ok(())
     }
 }
 
-impl std::error::Error for OmniError {}
+impl std::error::Error for OmniError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.cause
+            .as_deref()
+            .map(|cause| cause as &(dyn std::error::Error + 'static))
+    }
+}
 
-impl Encode for OmniError {
+impl Encode for ErrorValue {
     fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
-        match (&self.message, self.fields.is_empty()) {
-            (Some(msg), true) => e.array(2)?.u32(self.code.into())?.str(msg.as_str())?,
-            (Some(msg), false) => e
-                .array(3)?
-                .u32(self.code.into())?
-                .str(msg.as_str())?
-                .encode(&self.fields)?,
-            (None, true) => e.array(1)?.u32(self.code.into())?,
-            (None, false) => e.array(2)?.u32(self.code.into())?.encode(&self.fields)?,
-        };
+        match self {
+            ErrorValue::Str(s) => {
+                e.str(s)?;
+            }
+            ErrorValue::Int(n) => {
+                e.tag(Tag::Unassigned(TAG_ERROR_VALUE_INT))?.i64(*n)?;
+            }
+            ErrorValue::Float(n) => {
+                e.tag(Tag::Unassigned(TAG_ERROR_VALUE_FLOAT))?.f64(*n)?;
+            }
+            ErrorValue::Bool(b) => {
+                e.tag(Tag::Unassigned(TAG_ERROR_VALUE_BOOL))?.bool(*b)?;
+            }
+            ErrorValue::Timestamp(t) => {
+                e.tag(Tag::Unassigned(TAG_ERROR_VALUE_TIMESTAMP))?.i64(*t)?;
+            }
+        }
         Ok(())
     }
 }
 
-impl<'b> Decode<'b> for OmniError {
+impl<'b> Decode<'b> for ErrorValue {
     fn decode(d: &mut Decoder<'b>) -> Result<Self, minicbor::decode::Error> {
+        if d.datatype()? == Type::Tag {
+            let tag = d.tag()?;
+            return Ok(match tag {
+                Tag::Unassigned(TAG_ERROR_VALUE_INT) => ErrorValue::Int(d.i64()?),
+                Tag::Unassigned(TAG_ERROR_VALUE_FLOAT) => ErrorValue::Float(d.f64()?),
+                Tag::Unassigned(TAG_ERROR_VALUE_BOOL) => ErrorValue::Bool(d.bool()?),
+                Tag::Unassigned(TAG_ERROR_VALUE_TIMESTAMP) => ErrorValue::Timestamp(d.i64()?),
+                _ => ErrorValue::Str(d.str()?.to_string()),
+            });
+        }
+        Ok(ErrorValue::Str(d.str()?.to_string()))
+    }
+}
+
+impl Encode for OmniError {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
+        // The array only ever grows with trailing optional elements (fields,
+        // then labels, then cause), so older decoders that don't know about
+        // a given slot yet still read the shapes they always have.
+        let len = 1
+            + self.message.is_some() as u64
+            + !self.fields.is_empty() as u64
+            + !self.labels.is_empty() as u64
+            + self.cause.is_some() as u64;
+
+        e.array(len)?.u32(self.code.into())?;
+
+        if let Some(msg) = &self.message {
+            e.str(msg.as_str())?;
+        }
+        if !self.fields.is_empty() {
+            e.encode(&self.fields)?;
+        }
+        if !self.labels.is_empty() {
+            e.encode(&self.labels)?;
+        }
+        if let Some(cause) = &self.cause {
+            e.tag(Tag::Unassigned(TAG_ERROR_CAUSE))?.encode(cause.as_ref())?;
+        }
+        Ok(())
+    }
+}
+
+impl OmniError {
+    fn decode_with_depth<'b>(
+        d: &mut Decoder<'b>,
+        depth: usize,
+    ) -> Result<Self, minicbor::decode::Error> {
         d.array()?;
         let code: OmniErrorCode = d.u32()?.into();
 
-        if code.is_application_specific() {
-            Ok(Self {
-                code,
-                message: Some(d.str()?.to_string()),
-                fields: match d.datatype() {
-                    Ok(Type::Map) => d.decode()?,
-                    _ => BTreeMap::new(),
-                },
-            })
+        let message = if code.is_application_specific() {
+            Some(d.str()?.to_string())
         } else {
-            Ok(Self {
-                code,
-                message: None,
-                fields: match d.datatype() {
-                    Ok(Type::Map) => d.decode()?,
-                    _ => BTreeMap::new(),
-                },
-            })
-        }
+            None
+        };
+
+        let fields = match d.datatype() {
+            Ok(Type::Map) => d.decode()?,
+            _ => BTreeMap::new(),
+        };
+
+        // A plain array here is the labels list; a tagged value is the
+        // (also array-shaped) cause, so the tag is what disambiguates them.
+        let labels = match d.datatype() {
+            Ok(Type::Array) => d.decode()?,
+            _ => Vec::new(),
+        };
+
+        let cause = match d.datatype() {
+            Ok(Type::Tag) => {
+                if d.tag()? != Tag::Unassigned(TAG_ERROR_CAUSE) {
+                    return Err(minicbor::decode::Error::message(
+                        "Unexpected tag in OmniError cause slot.",
+                    ));
+                }
+                if depth >= MAX_ERROR_CAUSE_DEPTH {
+                    return Err(minicbor::decode::Error::message(
+                        "OmniError cause chain is too deep.",
+                    ));
+                }
+                Some(Box::new(OmniError::decode_with_depth(d, depth + 1)?))
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            code,
+            message,
+            fields,
+            labels,
+            cause,
+        })
+    }
+}
+
+impl<'b> Decode<'b> for OmniError {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, minicbor::decode::Error> {
+        OmniError::decode_with_depth(d, 0)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::message::error::ErrorLabel;
+    use crate::message::error::ErrorValue;
     use crate::message::error::OmniErrorCode as ErrorCode;
     use crate::OmniError;
     use std::collections::BTreeMap;
@@ -218,14 +623,16 @@ mod tests {
     #[test]
     fn works() {
         let mut fields = BTreeMap::new();
-        fields.insert("0".to_string(), "ZERO".to_string());
-        fields.insert("1".to_string(), "ONE".to_string());
-        fields.insert("2".to_string(), "TWO".to_string());
+        fields.insert("0".to_string(), ErrorValue::Str("ZERO".to_string()));
+        fields.insert("1".to_string(), ErrorValue::Str("ONE".to_string()));
+        fields.insert("2".to_string(), ErrorValue::Str("TWO".to_string()));
 
         let e = OmniError {
             code: ErrorCode::Unknown,
             message: Some("Hello {0} and {2}.".to_string()),
             fields,
+            labels: Vec::new(),
+            cause: None,
         };
 
         assert_eq!(format!("{}", e), "Hello ZERO and TWO.");
@@ -234,14 +641,16 @@ mod tests {
     #[test]
     fn works_with_only_replacement() {
         let mut fields = BTreeMap::new();
-        fields.insert("0".to_string(), "ZERO".to_string());
-        fields.insert("1".to_string(), "ONE".to_string());
-        fields.insert("2".to_string(), "TWO".to_string());
+        fields.insert("0".to_string(), ErrorValue::Str("ZERO".to_string()));
+        fields.insert("1".to_string(), ErrorValue::Str("ONE".to_string()));
+        fields.insert("2".to_string(), ErrorValue::Str("TWO".to_string()));
 
         let e = OmniError {
             code: ErrorCode::Unknown,
             message: Some("{2}".to_string()),
             fields,
+            labels: Vec::new(),
+            cause: None,
         };
 
         assert_eq!(format!("{}", e), "TWO");
@@ -250,14 +659,16 @@ mod tests {
     #[test]
     fn works_for_others() {
         let mut fields = BTreeMap::new();
-        fields.insert("0".to_string(), "ZERO".to_string());
-        fields.insert("1".to_string(), "ONE".to_string());
-        fields.insert("2".to_string(), "TWO".to_string());
+        fields.insert("0".to_string(), ErrorValue::Str("ZERO".to_string()));
+        fields.insert("1".to_string(), ErrorValue::Str("ONE".to_string()));
+        fields.insert("2".to_string(), ErrorValue::Str("TWO".to_string()));
 
         let e = OmniError {
             code: ErrorCode::Unknown,
             message: Some("@{a}{b}{c}.".to_string()),
             fields,
+            labels: Vec::new(),
+            cause: None,
         };
 
         assert_eq!(format!("{}", e), "@.");
@@ -266,16 +677,196 @@ mod tests {
     #[test]
     fn supports_double_brackets() {
         let mut fields = BTreeMap::new();
-        fields.insert("0".to_string(), "ZERO".to_string());
-        fields.insert("1".to_string(), "ONE".to_string());
-        fields.insert("2".to_string(), "TWO".to_string());
+        fields.insert("0".to_string(), ErrorValue::Str("ZERO".to_string()));
+        fields.insert("1".to_string(), ErrorValue::Str("ONE".to_string()));
+        fields.insert("2".to_string(), ErrorValue::Str("TWO".to_string()));
 
         let e = OmniError {
             code: ErrorCode::Unknown,
             message: Some("/{{}}{{{0}}}{{{a}}}{b}}}{{{2}.".to_string()),
             fields,
+            labels: Vec::new(),
+            cause: None,
         };
 
         assert_eq!(format!("{}", e), "/{}{ZERO}{}}{TWO.");
     }
+
+    #[test]
+    fn renders_conversions() {
+        let mut fields = BTreeMap::new();
+        fields.insert("max".to_string(), ErrorValue::Int(2048));
+        fields.insert("count".to_string(), ErrorValue::Int(42));
+        fields.insert("avg".to_string(), ErrorValue::Float(1.5));
+        fields.insert("ok".to_string(), ErrorValue::Bool(true));
+        fields.insert("when".to_string(), ErrorValue::Timestamp(0));
+
+        let e = OmniError {
+            code: ErrorCode::Unknown,
+            message: Some(
+                "{max:bytes} {count:integer} {avg:float} {ok:boolean} {when:timestamp} {when:timestamp(%Y)}"
+                    .to_string(),
+            ),
+            fields,
+            labels: Vec::new(),
+            cause: None,
+        };
+
+        assert_eq!(
+            format!("{}", e),
+            "2.00 KiB 42 1.5 true 1970-01-01T00:00:00Z 1970"
+        );
+    }
+
+    #[test]
+    fn falls_back_on_malformed_spec() {
+        let mut fields = BTreeMap::new();
+        fields.insert("n".to_string(), ErrorValue::Int(7));
+
+        let e = OmniError {
+            code: ErrorCode::Unknown,
+            message: Some("{n:not_a_real_spec}".to_string()),
+            fields,
+            labels: Vec::new(),
+            cause: None,
+        };
+
+        assert_eq!(format!("{}", e), "7");
+    }
+
+    #[test]
+    fn error_value_roundtrips_through_cbor() {
+        for value in [
+            ErrorValue::Str("hello".to_string()),
+            ErrorValue::Int(-42),
+            ErrorValue::Float(1.25),
+            ErrorValue::Bool(true),
+            ErrorValue::Timestamp(1_700_000_000),
+        ] {
+            let bytes = minicbor::to_vec(&value).unwrap();
+            let decoded: ErrorValue = minicbor::decode(&bytes).unwrap();
+            assert_eq!(decoded, value);
+        }
+    }
+
+    #[test]
+    fn renders_labels() {
+        assert_eq!(format!("{}", ErrorLabel::new("plain")), "plain");
+        assert_eq!(
+            format!("{}", ErrorLabel::new("bad span").at_span(3..7)),
+            "[3..7] bad span"
+        );
+        assert_eq!(
+            format!("{}", ErrorLabel::new("bad field").at_method("ledger.send.memo")),
+            "[ledger.send.memo] bad field"
+        );
+    }
+
+    #[test]
+    fn label_roundtrips_through_cbor() {
+        for label in [
+            ErrorLabel::new("plain"),
+            ErrorLabel::new("bad span").at_span(3..7),
+            ErrorLabel::new("bad field").at_method("ledger.send.memo"),
+        ] {
+            let bytes = minicbor::to_vec(&label).unwrap();
+            let decoded: ErrorLabel = minicbor::decode(&bytes).unwrap();
+            assert_eq!(decoded, label);
+        }
+    }
+
+    #[test]
+    fn omni_error_roundtrips_labels_through_cbor() {
+        let e = OmniError::unknown().with_label(ErrorLabel::new("bad span").at_span(3..7));
+
+        let bytes = minicbor::to_vec(&e).unwrap();
+        let decoded: OmniError = minicbor::decode(&bytes).unwrap();
+        assert_eq!(decoded.labels, e.labels);
+    }
+
+    #[test]
+    fn cause_chain_roundtrips_through_cbor() {
+        let root = OmniError::unknown()
+            .with_cause(OmniError::internal_server_error().with_cause(OmniError::empty_envelope()));
+
+        let bytes = minicbor::to_vec(&root).unwrap();
+        let decoded: OmniError = minicbor::decode(&bytes).unwrap();
+
+        let mid = decoded.cause.as_deref().expect("missing first cause");
+        let leaf = mid.cause.as_deref().expect("missing second cause");
+
+        assert!(matches!(decoded.code, ErrorCode::Unknown));
+        assert!(matches!(mid.code, ErrorCode::InternalServerError));
+        assert!(matches!(leaf.code, ErrorCode::EmptyEnvelope));
+        assert!(leaf.cause.is_none());
+    }
+
+    #[test]
+    fn cause_chain_exposes_source() {
+        use std::error::Error;
+
+        let e = OmniError::unknown().with_cause(OmniError::internal_server_error());
+        assert!(e.source().is_some());
+        assert!(OmniError::unknown().source().is_none());
+    }
+
+    #[test]
+    fn cause_chain_over_depth_limit_fails_to_decode() {
+        let mut e = OmniError::unknown();
+        for _ in 0..=super::MAX_ERROR_CAUSE_DEPTH {
+            e = OmniError::unknown().with_cause(e);
+        }
+
+        let bytes = minicbor::to_vec(&e).unwrap();
+        let decoded: Result<OmniError, _> = minicbor::decode(&bytes);
+        assert!(decoded.is_err());
+    }
+
+    omni_app_error! {
+        20000: sample_app_error() => "A sample application error.",
+    }
+
+    #[test]
+    fn registered_application_codes_resolve_their_message() {
+        super::register_error_codes(ERROR_CODES);
+        assert_eq!(
+            super::registered_message(20000),
+            Some("A sample application error.")
+        );
+        assert_eq!(
+            ErrorCode::message_of(20000),
+            Some("A sample application error.")
+        );
+    }
+
+    #[test]
+    fn omni_app_error_roundtrips_through_cbor() {
+        let e = sample_app_error();
+        let bytes = minicbor::to_vec(&e).unwrap();
+        let decoded: OmniError = minicbor::decode(&bytes).unwrap();
+        assert_eq!(format!("{}", decoded), format!("{}", e));
+    }
+
+    mod bad_app_error {
+        omni_app_error! {
+            1: below_reserved_range() => "Below RESERVED_OMNI_ERROR_CODE.",
+        }
+    }
+
+    #[test]
+    fn omni_app_error_rejects_a_code_below_the_reserved_range() {
+        let result = std::panic::catch_unwind(bad_app_error::below_reserved_range);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn rejecting_a_bad_batch_does_not_poison_the_registry() {
+        let bad_batch: &[(u32, &'static str)] = &[(1, "Below RESERVED_OMNI_ERROR_CODE.")];
+        let result = std::panic::catch_unwind(|| super::register_error_codes(bad_batch));
+        assert!(result.is_err());
+
+        // The registry must still be usable after the rejected batch panicked.
+        super::register_error_codes(&[(20001, "Still works.")]);
+        assert_eq!(super::registered_message(20001), Some("Still works."));
+    }
 }
@@ -0,0 +1,150 @@
+use crate::storage::LedgerStorageBackend;
+use many::ManyError;
+use minicbor::encode::{Error, Write};
+use minicbor::{Decode, Decoder, Encode, Encoder};
+use sha3::{Digest, Sha3_256};
+use std::io::Read;
+
+const CONTENT_KEY_PREFIX: &[u8] = b"content/";
+
+fn content_key(id: &[u8]) -> Vec<u8> {
+    [CONTENT_KEY_PREFIX, id].concat()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Content-addressed blob store layered over any `LedgerStorageBackend`.
+/// Blobs are keyed by their own SHA3-256 digest under a `content/` prefix.
+pub struct BlobStorage<'a, B: LedgerStorageBackend> {
+    backend: &'a mut B,
+}
+
+impl<'a, B: LedgerStorageBackend> BlobStorage<'a, B> {
+    pub fn new(backend: &'a mut B) -> Self {
+        Self { backend }
+    }
+
+    /// Stream `reader` into the store, hashing it incrementally. If
+    /// `expected_id` is provided, the computed digest must match it exactly.
+    pub fn put(
+        &mut self,
+        mut reader: impl Read,
+        expected_id: Option<&[u8]>,
+    ) -> Result<Vec<u8>, ManyError> {
+        let mut hasher = Sha3_256::new();
+        let mut buffer = Vec::new();
+        let mut chunk = [0u8; 8192];
+
+        loop {
+            let n = reader
+                .read(&mut chunk)
+                .map_err(|e| ManyError::unknown(e.to_string()))?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&chunk[..n]);
+            buffer.extend_from_slice(&chunk[..n]);
+        }
+
+        let id = hasher.finalize().to_vec();
+        if let Some(expected) = expected_id {
+            if expected != id.as_slice() {
+                return Err(ManyError::unknown(format!(
+                    "Content digest mismatch: expected {}, computed {}.",
+                    hex(expected),
+                    hex(&id)
+                )));
+            }
+        }
+
+        self.backend.put(content_key(&id), buffer);
+        Ok(id)
+    }
+
+    /// Fetch a blob alongside the id it is stored under.
+    pub fn get(&self, id: &[u8]) -> Result<Option<(Vec<u8>, Vec<u8>)>, ManyError> {
+        Ok(self
+            .backend
+            .get_staged(&content_key(id))?
+            .map(|value| (id.to_vec(), value)))
+    }
+
+    /// Fetch a blob in the `(id, value)` shape a query attribute returns.
+    pub fn get_blob(&self, id: &[u8]) -> Result<Option<Blob>, ManyError> {
+        Ok(self
+            .get(id)?
+            .map(|(id, value)| Blob { id, value }))
+    }
+}
+
+/// A blob together with the id it is stored under, as returned by
+/// [`BlobStorage::get_blob`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Blob {
+    pub id: Vec<u8>,
+    pub value: Vec<u8>,
+}
+
+impl Encode for Blob {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
+        e.array(2)?.bytes(&self.id)?.bytes(&self.value)?;
+        Ok(())
+    }
+}
+
+impl<'b> Decode<'b> for Blob {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, minicbor::decode::Error> {
+        d.array()?;
+        let id = d.bytes()?.to_vec();
+        let value = d.bytes()?.to_vec();
+        Ok(Blob { id, value })
+    }
+}
+
+#[test]
+fn blob_roundtrip() {
+    use crate::storage::merk::MerkStorageBackend;
+
+    let persistent_path = tempfile::tempdir().unwrap();
+    let mut db = MerkStorageBackend::create(persistent_path).unwrap();
+    let mut blobs = BlobStorage::new(&mut db);
+
+    let id = blobs.put("hello world".as_bytes(), None).unwrap();
+    let (returned_id, value) = blobs.get(&id).unwrap().unwrap();
+    assert_eq!(returned_id, id);
+    assert_eq!(value, b"hello world");
+}
+
+#[test]
+fn blob_query_roundtrip() {
+    use crate::storage::merk::MerkStorageBackend;
+
+    let persistent_path = tempfile::tempdir().unwrap();
+    let mut db = MerkStorageBackend::create(persistent_path).unwrap();
+    let mut blobs = BlobStorage::new(&mut db);
+
+    let id = blobs.put("hello world".as_bytes(), None).unwrap();
+    let blob = blobs.get_blob(&id).unwrap().unwrap();
+    assert_eq!(blob.id, id);
+    assert_eq!(blob.value, b"hello world");
+
+    let bytes = minicbor::to_vec(&blob).unwrap();
+    let decoded: Blob = minicbor::decode(&bytes).unwrap();
+    assert_eq!(decoded, blob);
+
+    assert!(blobs.get_blob(&[0u8; 32]).unwrap().is_none());
+}
+
+#[test]
+fn blob_rejects_digest_mismatch() {
+    use crate::storage::merk::MerkStorageBackend;
+
+    let persistent_path = tempfile::tempdir().unwrap();
+    let mut db = MerkStorageBackend::create(persistent_path).unwrap();
+    let mut blobs = BlobStorage::new(&mut db);
+
+    let wrong_id = vec![0u8; 32];
+    assert!(blobs.put("hello world".as_bytes(), Some(&wrong_id)).is_err());
+}
@@ -1,8 +1,12 @@
 use crate::storage::{Direction, LedgerStorageBackend};
 use many::ManyError;
+use merk::proofs::query::verify;
+use merk::proofs::Query;
 use merk::tree::Tree;
 use merk::Merk;
 use merk::{rocksdb, BatchEntry, Op};
+use minicbor::encode::{Error, Write};
+use minicbor::{Decode, Decoder, Encode, Encoder};
 use std::borrow::Cow;
 use std::collections::Bound::*;
 use std::collections::{BTreeMap, Bound};
@@ -24,6 +28,51 @@ fn incr(mut v: Vec<u8>) -> Vec<u8> {
     tmp
 }
 
+/// Insert a `start..end` bound pair into a Merk `Query`.
+fn insert_bounded_range(query: &mut Query, start: Bound<Vec<u8>>, end: Bound<Vec<u8>>) {
+    match (start, end) {
+        (Included(s), Included(e)) => query.insert_range_inclusive(s..=e),
+        (Included(s), Excluded(e)) => query.insert_range(s..e),
+        (Included(s), Unbounded) => query.insert_range_from(s..),
+        (Excluded(s), Included(e)) => query.insert_range_inclusive(incr(s)..=e),
+        (Excluded(s), Excluded(e)) => query.insert_range(incr(s)..e),
+        (Excluded(s), Unbounded) => query.insert_range_from(incr(s)..),
+        (Unbounded, Included(e)) => query.insert_range_to_inclusive(..=e),
+        (Unbounded, Excluded(e)) => query.insert_range_to(..e),
+        (Unbounded, Unbounded) => query.insert_all(),
+    }
+}
+
+/// Authenticate a proof against an expected root hash and return the value
+/// of each of `keys` (`None` for keys proven absent). Other pairs the proof
+/// covers are not returned.
+pub fn verify_proof(
+    proof: &[u8],
+    root_hash: &[u8],
+    keys: &[Vec<u8>],
+) -> Result<BTreeMap<Vec<u8>, Option<Vec<u8>>>, ManyError> {
+    let mut expected_hash = [0u8; 32];
+    if root_hash.len() != expected_hash.len() {
+        return Err(ManyError::unknown(format!(
+            "Root hash must be {} bytes.",
+            expected_hash.len()
+        )));
+    }
+    expected_hash.copy_from_slice(root_hash);
+
+    let map = verify(proof, expected_hash).map_err(|e| ManyError::unknown(e.to_string()))?;
+
+    let mut result = BTreeMap::new();
+    for key in keys {
+        let value = map
+            .get(key)
+            .map_err(|e| ManyError::unknown(e.to_string()))?
+            .map(|v| v.to_vec());
+        result.insert(key.clone(), value);
+    }
+    Ok(result)
+}
+
 /// Storage backend which uses Merk as the persistent store.
 /// This is Sync and Send, even though the ledger module isn't (yet).
 /// It also allows for re-entry.
@@ -43,6 +92,64 @@ impl MerkStorageBackend {
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ManyError> {
         Self::create(path)
     }
+
+    /// Produce a proof of the given keys against the current root hash.
+    pub fn prove(&self, keys: &[Vec<u8>]) -> Result<Vec<u8>, ManyError> {
+        let mut query = Query::new();
+        for key in keys {
+            query.insert_key(key.clone());
+        }
+
+        self.merk
+            .prove(query)
+            .map_err(|e| ManyError::unknown(e.to_string()))
+    }
+
+    /// Produce a proof over a range of keys, for authenticating iterator
+    /// results.
+    pub fn prove_range(
+        &self,
+        start: Bound<Vec<u8>>,
+        end: Bound<Vec<u8>>,
+    ) -> Result<Vec<u8>, ManyError> {
+        let mut query = Query::new();
+        insert_bounded_range(&mut query, start, end);
+
+        self.merk
+            .prove(query)
+            .map_err(|e| ManyError::unknown(e.to_string()))
+    }
+
+    /// Fetch a value along with a proof of it.
+    pub fn get_with_proof(&self, key: &[u8]) -> Result<ProvenValue, ManyError> {
+        Ok(ProvenValue {
+            value: self.get(key)?,
+            proof: self.prove(std::slice::from_ref(&key.to_vec()))?,
+        })
+    }
+}
+
+/// A value together with its proof, as returned by [`MerkStorageBackend::get_with_proof`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProvenValue {
+    pub value: Option<Vec<u8>>,
+    pub proof: Vec<u8>,
+}
+
+impl Encode for ProvenValue {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), Error<W::Error>> {
+        e.array(2)?.encode(&self.value)?.bytes(&self.proof)?;
+        Ok(())
+    }
+}
+
+impl<'b> Decode<'b> for ProvenValue {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, minicbor::decode::Error> {
+        d.array()?;
+        let value = d.decode()?;
+        let proof = d.bytes()?.to_vec();
+        Ok(ProvenValue { value, proof })
+    }
 }
 
 impl LedgerStorageBackend for MerkStorageBackend {
@@ -179,3 +286,79 @@ fn iterator_works() {
     check(&db, Unbounded, Excluded(4), vec![1, 2, 3]);
     check(&db, Unbounded, Unbounded, vec![1, 2, 3, 4, 5]);
 }
+
+#[test]
+fn prove_roundtrip() {
+    let persistent_path = tempfile::tempdir().unwrap();
+    let mut db = MerkStorageBackend::create(persistent_path).unwrap();
+
+    for i in 1..=5u64 {
+        let b = u64::to_be_bytes(i).to_vec();
+        db.put(b.clone(), b);
+    }
+    db.commit().unwrap();
+
+    let present = u64::to_be_bytes(3).to_vec();
+    let absent = u64::to_be_bytes(42).to_vec();
+    let keys = vec![present.clone(), absent.clone()];
+
+    let proof = db.prove(&keys).unwrap();
+    let authenticated = verify_proof(&proof, &db.hash(), &keys).unwrap();
+
+    assert_eq!(authenticated.get(&present).unwrap(), &Some(present.clone()));
+    assert_eq!(authenticated.get(&absent).unwrap(), &None);
+}
+
+#[test]
+fn prove_range_roundtrip() {
+    fn check(db: &MerkStorageBackend, start: Bound<u64>, end: Bound<u64>, expected: Vec<u64>) {
+        fn to_vec(b: Bound<u64>) -> Bound<Vec<u8>> {
+            match b {
+                Included(s) => Included(s.to_be_bytes().to_vec()),
+                Excluded(s) => Excluded(s.to_be_bytes().to_vec()),
+                Unbounded => Unbounded,
+            }
+        }
+
+        let proof = db
+            .prove_range(to_vec(start), to_vec(end))
+            .unwrap();
+
+        let expected_keys: Vec<Vec<u8>> = expected
+            .iter()
+            .map(|i| i.to_be_bytes().to_vec())
+            .collect();
+        let authenticated = verify_proof(&proof, &db.hash(), &expected_keys).unwrap();
+
+        for key in &expected_keys {
+            assert!(authenticated.get(key).unwrap().is_some());
+        }
+    }
+
+    let persistent_path = tempfile::tempdir().unwrap();
+    let mut db = MerkStorageBackend::create(persistent_path).unwrap();
+
+    for i in 1..=5u64 {
+        let b = u64::to_be_bytes(i).to_vec();
+        db.put(b.clone(), b);
+    }
+    db.commit().unwrap();
+
+    check(&db, Included(2), Included(4), vec![2, 3, 4]);
+    check(&db, Included(2), Excluded(4), vec![2, 3]);
+    check(&db, Excluded(2), Included(4), vec![3, 4]);
+    check(&db, Excluded(2), Excluded(4), vec![3]);
+    check(&db, Included(2), Unbounded, vec![2, 3, 4, 5]);
+    check(&db, Excluded(2), Unbounded, vec![3, 4, 5]);
+    check(&db, Unbounded, Included(4), vec![1, 2, 3, 4]);
+    check(&db, Unbounded, Excluded(4), vec![1, 2, 3]);
+    check(&db, Unbounded, Unbounded, vec![1, 2, 3, 4, 5]);
+
+    // A key outside the proven range must not verify as present.
+    let proof = db
+        .prove_range(Included(2u64.to_be_bytes().to_vec()), Included(4u64.to_be_bytes().to_vec()))
+        .unwrap();
+    let outside = 5u64.to_be_bytes().to_vec();
+    let authenticated = verify_proof(&proof, &db.hash(), &[outside.clone()]).unwrap();
+    assert_eq!(authenticated.get(&outside).unwrap(), &None);
+}